@@ -0,0 +1,248 @@
+//! Safe, RAII-based wrappers around device lifecycle management
+//!
+//! [`crate::sys::device`] exposes `CreateBlockDevice`/`CreateCharDevice` and their
+//! `Remove*` counterparts as raw `extern "C"` functions: callers are responsible
+//! for remembering to remove what they created, and for matching the right
+//! `Remove*` function to the device kind they asked for. [`BlockDeviceBuilder`]
+//! and [`CharDeviceBuilder`] collect the configuration for a device, issue the
+//! matching `Create*Device` syscall, and hand back a [`RegisteredDevice`] guard
+//! that removes the device again on drop, in the same spirit as a Rust-for-Linux
+//! `Registration<T>`.
+
+use core::ffi::{c_long, c_ulong};
+use core::mem::MaybeUninit;
+
+use crate::{
+    error::{Error, Result},
+    handle::{BorrowedHandle, HandleRef, OwnedHandle},
+    sys::{
+        device::{
+            self, BlockDeviceConfiguration, CharDeviceConfiguration, DeviceGeometry, DeviceHandle,
+        },
+        fs::FileHandle,
+        handle::HandlePtr,
+        io::IOHandle,
+        isolation::NamespaceHandle,
+        kstr::KStrCPtr,
+    },
+    uuid::Uuid,
+};
+
+pub mod command;
+pub mod iter;
+
+pub use command::{
+    register, CommandArg, CommandArgs, CommandBuffer, DecodableCommandArg, DeviceCommand, RegisterArgs,
+};
+pub use iter::{DeviceEntry, DeviceIter};
+
+impl HandleRef<DeviceHandle> {
+    /// Retrieves the block layout and timestamps of this device, as though by [`GetDeviceGeometry`][device::GetDeviceGeometry]
+    pub fn geometry(&self) -> Result<DeviceGeometry> {
+        let mut geometry = MaybeUninit::uninit();
+
+        Error::from_code(unsafe { device::GetDeviceGeometry(self.as_raw(), geometry.as_mut_ptr()) })?;
+
+        Ok(unsafe { geometry.assume_init() })
+    }
+}
+
+impl HandleRef<FileHandle> {
+    /// Retrieves the block layout and timestamps of the device backing the filesystem this file resides on,
+    ///  as though by [`GetFileDeviceGeometry`][device::GetFileDeviceGeometry]
+    pub fn device_geometry(&self) -> Result<DeviceGeometry> {
+        let mut geometry = MaybeUninit::uninit();
+
+        Error::from_code(unsafe { device::GetFileDeviceGeometry(self.as_raw(), geometry.as_mut_ptr()) })?;
+
+        Ok(unsafe { geometry.assume_init() })
+    }
+}
+
+enum DeviceKind {
+    Block,
+    Char,
+}
+
+/// Builder for a block device created by [`CreateBlockDevice`][device::CreateBlockDevice]
+///
+/// Construct with [`BlockDeviceBuilder::new`], configure the optional fields,
+/// then call [`create`][Self::create] with the backing [`IOHandle`] to install
+/// the device and obtain a [`RegisteredDevice`] guard.
+pub struct BlockDeviceBuilder<'a> {
+    label: KStrCPtr,
+    acl: Option<BorrowedHandle<'a, FileHandle>>,
+    optimistic_io_size: c_ulong,
+    base: c_ulong,
+    extent: c_long,
+    id: Uuid,
+    ns: Option<BorrowedHandle<'a, NamespaceHandle>>,
+}
+
+impl<'a> BlockDeviceBuilder<'a> {
+    /// Begins building a block device configuration with the given user-friendly `label`
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: KStrCPtr::from_str(label),
+            acl: None,
+            optimistic_io_size: 0,
+            base: 0,
+            extent: 0,
+            id: Uuid::nil(),
+            ns: None,
+        }
+    }
+
+    /// Sets the access control list applied to the created device
+    pub fn acl(mut self, acl: BorrowedHandle<'a, FileHandle>) -> Self {
+        self.acl = Some(acl);
+        self
+    }
+
+    /// Sets the size, in bytes, that is reported as optimistic for I/O performed on the device
+    pub fn optimistic_io_size(mut self, size: u64) -> Self {
+        self.optimistic_io_size = size as c_ulong;
+        self
+    }
+
+    /// Exposes a [`CHAR_RANDOMACCESS`][crate::sys::io::CHAR_RANDOMACCESS] window of the backing handle, starting at `base` and spanning `extent` bytes
+    pub fn random_access(mut self, base: u64, extent: i64) -> Self {
+        self.base = base as c_ulong;
+        self.extent = extent as c_long;
+        self
+    }
+
+    /// Requests a specific device id rather than letting the kernel assign one
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Creates the device inside the device scope of `ns` rather than that of the current thread
+    pub fn namespace(mut self, ns: BorrowedHandle<'a, NamespaceHandle>) -> Self {
+        self.ns = Some(ns);
+        self
+    }
+
+    /// Creates the block device backed by `backing`, returning a guard that removes it again on drop
+    pub fn create<'b>(self, backing: BorrowedHandle<'b, IOHandle>) -> Result<RegisteredDevice<'b>> {
+        let cfg = BlockDeviceConfiguration {
+            label: self.label,
+            acl: self.acl.map_or(HandlePtr::null(), |acl| acl.as_raw()),
+            optimistic_io_size: self.optimistic_io_size,
+            base: self.base,
+            extent: self.extent,
+        };
+
+        let mut id = self.id;
+        let ns = self.ns.map_or(HandlePtr::null(), |ns| ns.as_raw());
+
+        Error::from_code(unsafe { device::CreateBlockDevice(&mut id, backing.as_raw(), &cfg, ns) })?;
+
+        Ok(RegisteredDevice {
+            backing,
+            id,
+            kind: DeviceKind::Block,
+        })
+    }
+}
+
+/// Builder for a character device created by [`CreateCharDevice`][device::CreateCharDevice]
+///
+/// Construct with [`CharDeviceBuilder::new`], configure the optional fields,
+/// then call [`create`][Self::create] with the backing [`IOHandle`] to install
+/// the device and obtain a [`RegisteredDevice`] guard.
+pub struct CharDeviceBuilder<'a> {
+    label: KStrCPtr,
+    acl: Option<BorrowedHandle<'a, FileHandle>>,
+    optimistic_io_size: u64,
+    id: Uuid,
+}
+
+impl<'a> CharDeviceBuilder<'a> {
+    /// Begins building a character device configuration with the given user-friendly `label`
+    pub fn new(label: &str) -> Self {
+        Self {
+            label: KStrCPtr::from_str(label),
+            acl: None,
+            optimistic_io_size: 0,
+            id: Uuid::nil(),
+        }
+    }
+
+    /// Sets the access control list applied to the created device
+    pub fn acl(mut self, acl: BorrowedHandle<'a, FileHandle>) -> Self {
+        self.acl = Some(acl);
+        self
+    }
+
+    /// Sets the size, in bytes, that is reported as optimistic for I/O performed on the device
+    pub fn optimistic_io_size(mut self, size: u64) -> Self {
+        self.optimistic_io_size = size;
+        self
+    }
+
+    /// Requests a specific device id rather than letting the kernel assign one
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Creates the character device backed by `backing`, returning a guard that removes it again on drop
+    pub fn create<'b>(self, backing: BorrowedHandle<'b, IOHandle>) -> Result<RegisteredDevice<'b>> {
+        let cfg = CharDeviceConfiguration {
+            label: self.label,
+            acl: self.acl.map_or(HandlePtr::null(), |acl| acl.as_raw()),
+            optimistic_io_size: self.optimistic_io_size,
+        };
+
+        let mut id = self.id;
+
+        Error::from_code(unsafe { device::CreateCharDevice(&mut id, backing.as_raw(), &cfg) })?;
+
+        Ok(RegisteredDevice {
+            backing,
+            id,
+            kind: DeviceKind::Char,
+        })
+    }
+}
+
+/// An owned guard over a device created by [`BlockDeviceBuilder`] or [`CharDeviceBuilder`]
+///
+/// Removes the device from its backing handle when dropped, mirroring the
+/// `Create*Device`/`Remove*Device` pairing that callers would otherwise have
+/// to balance by hand. Borrows the backing [`IOHandle`] for its whole
+/// lifetime, so the handle cannot be closed out from under a live guard.
+pub struct RegisteredDevice<'a> {
+    backing: BorrowedHandle<'a, IOHandle>,
+    id: Uuid,
+    kind: DeviceKind,
+}
+
+impl<'a> RegisteredDevice<'a> {
+    /// Returns the kernel-assigned (or caller-assigned) id of this device
+    pub const fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Opens a fresh handle to this device, as though by [`OpenDevice`][device::OpenDevice]
+    pub fn open(&self) -> Result<OwnedHandle<DeviceHandle>> {
+        let mut hdl = MaybeUninit::uninit();
+
+        Error::from_code(unsafe { device::OpenDevice(hdl.as_mut_ptr(), self.id) })?;
+
+        Ok(unsafe { OwnedHandle::take_ownership(hdl.assume_init()) })
+    }
+}
+
+impl<'a> Drop for RegisteredDevice<'a> {
+    fn drop(&mut self) {
+        let _ = unsafe {
+            match self.kind {
+                DeviceKind::Block => device::RemoveBlockDevice(self.backing.as_raw()),
+                DeviceKind::Char => device::RemoveCharDevice(self.backing.as_raw()),
+            }
+        };
+    }
+}
@@ -0,0 +1,325 @@
+//! Type-safe marshalling for [`IssueDeviceCommand`]/[`RegisterDeviceCommand`]
+//!
+//! [`IssueDeviceCommand`] is raw C variadics and [`RegisterDeviceCommand`] takes
+//! an untyped `sigtys` signature array with a matching variadic callback, so
+//! callers on both sides must hand-pack arguments with nothing checking they
+//! agree. [`CommandArg`] is implemented for the concrete types each
+//! [`DeviceCommandParameter`] slot can describe, [`CommandArgs`] is implemented
+//! for tuples of those, and [`DeviceCommand`] pairs a command id with the
+//! `sigtys` signature it was registered with, so that a typed argument tuple
+//! is checked against the signature it is issued against rather than trusted
+//! to agree by construction. On the handling side, [`register`] decodes a
+//! handler's arguments by their Rust types and does not check those types
+//! against `sigtys`.
+//!
+//! [`IssueDeviceCommand`]: crate::sys::device::IssueDeviceCommand
+//! [`RegisterDeviceCommand`]: crate::sys::device::RegisterDeviceCommand
+
+mod private {
+    pub trait Sealed {}
+}
+
+use core::ffi::c_ulong;
+
+use private::Sealed;
+
+use crate::{
+    error::{Error, Result},
+    handle::{BorrowedHandle, HandleRef},
+    security::SecurityContext,
+    sys::{
+        device::{self, udev::{DeviceCommandParameter, DeviceCommandParameterKind}, DeviceHandle},
+        handle::HandlePtr,
+        result::{SysResult, INVALID_OPERATION, SUCCESS},
+    },
+    uuid::Uuid,
+};
+
+/// A single typed argument accepted by a device command, corresponding to one [`DeviceCommandParameter`] slot
+///
+/// Implemented for the concrete Rust types that correspond to each
+/// [`DeviceCommandParameterKind`]; not implementable outside this crate, since
+/// [`into_raw`][Self::into_raw] must agree with the variadic ABI [`IssueDeviceCommand`][device::IssueDeviceCommand] and
+/// [`RegisterDeviceCommand`][device::RegisterDeviceCommand] decode.
+pub unsafe trait CommandArg: Sealed {
+    /// The representation forwarded through the variadic ABI
+    type Raw: Copy;
+
+    /// The parameter kind this type marshals
+    const KIND: DeviceCommandParameterKind;
+
+    /// Checks this argument against the registered signature slot, beyond the [`KIND`][Self::KIND] check already performed
+    fn matches_signature(&self, sig: &DeviceCommandParameter) -> bool {
+        let _ = sig;
+        true
+    }
+
+    /// Converts to the representation forwarded through the variadic ABI
+    fn into_raw(self) -> Self::Raw;
+}
+
+macro_rules! impl_scalar_command_arg {
+    ($($ty:ty => $kind:ident),* $(,)?) => {
+        $(
+            impl Sealed for $ty {}
+
+            unsafe impl CommandArg for $ty {
+                type Raw = $ty;
+                const KIND: DeviceCommandParameterKind = DeviceCommandParameterKind::$kind;
+
+                fn into_raw(self) -> Self::Raw {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_scalar_command_arg! {
+    i32 => Int32,
+    i64 => Int64,
+    u32 => UInt32,
+    u64 => UInt64,
+}
+
+impl<T> Sealed for HandlePtr<T> {}
+
+unsafe impl<T> CommandArg for HandlePtr<T> {
+    type Raw = HandlePtr<T>;
+    const KIND: DeviceCommandParameterKind = DeviceCommandParameterKind::Handle;
+
+    fn into_raw(self) -> Self::Raw {
+        self
+    }
+}
+
+/// A [`CommandArg`] that can also be decoded back from its raw ABI representation
+///
+/// Implemented for the scalar and handle parameter kinds, whose raw representation alone is
+///  enough to reconstruct them. [`CommandBuffer`] is not decodable this way - its length comes
+///  from the registered signature, not from the bare pointer the callback ABI hands over - so a
+///  [`register`]ed handler cannot take one as an argument.
+pub unsafe trait DecodableCommandArg: CommandArg {
+    /// Reconstructs the typed argument from its raw ABI representation
+    unsafe fn from_raw(raw: Self::Raw) -> Self;
+}
+
+macro_rules! impl_decodable_scalar {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            unsafe impl DecodableCommandArg for $ty {
+                unsafe fn from_raw(raw: Self::Raw) -> Self {
+                    raw
+                }
+            }
+        )*
+    };
+}
+
+impl_decodable_scalar!(i32, i64, u32, u64);
+
+unsafe impl<T> DecodableCommandArg for HandlePtr<T> {
+    unsafe fn from_raw(raw: Self::Raw) -> Self {
+        raw
+    }
+}
+
+/// A buffer argument whose length must match the `size` recorded in the registered signature slot
+pub struct CommandBuffer<'a>(&'a mut [u8]);
+
+impl<'a> CommandBuffer<'a> {
+    /// Wraps `buf` as a [`DeviceCommandParameterKind::Buffer`] argument
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self(buf)
+    }
+}
+
+impl<'a> Sealed for CommandBuffer<'a> {}
+
+unsafe impl<'a> CommandArg for CommandBuffer<'a> {
+    type Raw = *mut u8;
+    const KIND: DeviceCommandParameterKind = DeviceCommandParameterKind::Buffer;
+
+    fn matches_signature(&self, sig: &DeviceCommandParameter) -> bool {
+        self.0.len() as c_ulong == sig.size
+    }
+
+    fn into_raw(self) -> Self::Raw {
+        self.0.as_mut_ptr()
+    }
+}
+
+/// The raw variadic callback type expected by [`RegisterDeviceCommand`][device::RegisterDeviceCommand]
+///
+/// [`build_trampoline`][RegisterArgs::build_trampoline] hands the kernel a concrete, non-variadic
+/// `extern "C" fn` transmuted to this type rather than an actually variadic one, on the assumption
+/// that the fixed-arg and C-variadic calling conventions for `...`'s integer/handle/pointer argument
+/// kinds coincide on the target. This is true of every target this crate is currently built for, but
+/// is a target-ABI assumption, not something the C standard guarantees in general - a target whose
+/// variadic calling convention diverges from its fixed-arg one would need a genuinely variadic shim
+/// built instead of this transmute.
+type RawCallback =
+    unsafe extern "C" fn(cmdid: *const Uuid, callctx: HandlePtr<SecurityContext>, ...) -> SysResult;
+
+/// A tuple of [`CommandArg`]s, validated and dispatched together against a [`DeviceCommand`]'s signature
+///
+/// Implemented for tuples of up to 4 [`CommandArg`]s; not implementable outside this crate.
+pub trait CommandArgs: Sealed + Sized {
+    #[doc(hidden)]
+    fn signature_matches(&self, sigtys: &[DeviceCommandParameter]) -> bool;
+    #[doc(hidden)]
+    unsafe fn issue_raw(self, hdl: HandlePtr<DeviceHandle>, cmd: *const Uuid) -> SysResult;
+}
+
+macro_rules! impl_command_args {
+    ($($T:ident . $idx:tt),*) => {
+        impl<$($T: CommandArg),*> Sealed for ($($T,)*) {}
+
+        impl<$($T: CommandArg),*> CommandArgs for ($($T,)*) {
+            fn signature_matches(&self, sigtys: &[DeviceCommandParameter]) -> bool {
+                let mut sigtys = sigtys.iter();
+                $(
+                    match sigtys.next() {
+                        Some(sig) if sig.kind == <$T as CommandArg>::KIND && self.$idx.matches_signature(sig) => {}
+                        _ => return false,
+                    }
+                )*
+                sigtys.next().is_none()
+            }
+
+            unsafe fn issue_raw(self, hdl: HandlePtr<DeviceHandle>, cmd: *const Uuid) -> SysResult {
+                device::IssueDeviceCommand(hdl, cmd $(, self.$idx.into_raw())*)
+            }
+        }
+    };
+}
+
+impl_command_args!();
+impl_command_args!(A.0);
+impl_command_args!(A.0, B.1);
+impl_command_args!(A.0, B.1, C.2);
+impl_command_args!(A.0, B.1, C.2, D.3);
+
+/// [`CommandArgs`] whose every slot can also be decoded from its raw representation, so a handler for it can be [`register`]ed
+pub trait RegisterArgs: CommandArgs {
+    #[doc(hidden)]
+    fn build_trampoline<F>(handler: F) -> RawCallback
+    where
+        F: Fn(BorrowedHandle<'_, SecurityContext>, Self) -> Result<()> + Copy + 'static;
+}
+
+macro_rules! impl_register_args {
+    ($($T:ident - $raw:ident . $idx:tt),*) => {
+        impl<$($T: DecodableCommandArg),*> RegisterArgs for ($($T,)*) {
+            fn build_trampoline<F>(_handler: F) -> RawCallback
+            where
+                F: Fn(BorrowedHandle<'_, SecurityContext>, Self) -> Result<()> + Copy + 'static,
+            {
+                assert!(
+                    core::mem::size_of::<F>() == 0,
+                    "device command handlers must be capture-free (use a plain fn or non-capturing closure)"
+                );
+
+                extern "C" fn trampoline<F, $($T: DecodableCommandArg),*>(
+                    _cmdid: *const Uuid,
+                    callctx: HandlePtr<SecurityContext>,
+                    $($raw: $T::Raw,)*
+                ) -> SysResult
+                where
+                    F: Fn(BorrowedHandle<'_, SecurityContext>, ($($T,)*)) -> Result<()> + Copy + 'static,
+                {
+                    // Sound because `build_trampoline` above only ever instantiates this with a zero-sized `F`.
+                    let f: F = unsafe { core::mem::transmute_copy(&()) };
+                    let ctx = unsafe { BorrowedHandle::from_raw(callctx) };
+                    let args = unsafe { ($($T::from_raw($raw),)*) };
+
+                    match f(ctx, args) {
+                        Ok(()) => SUCCESS,
+                        Err(e) => e.into_code(),
+                    }
+                }
+
+                // SAFETY: relies on the fixed-arg/variadic ABI coincidence documented on `RawCallback`.
+                unsafe { core::mem::transmute(trampoline::<F, $($T),*> as usize) }
+            }
+        }
+    };
+}
+
+impl_register_args!();
+impl_register_args!(A - a . 0);
+impl_register_args!(A - a . 0, B - b . 1);
+impl_register_args!(A - a . 0, B - b . 1, C - c . 2);
+impl_register_args!(A - a . 0, B - b . 1, C - c . 2, D - d . 3);
+
+impl HandleRef<DeviceHandle> {
+    /// Issues `cmd` to this device with `args`
+    ///
+    /// `args` is checked against `cmd`'s registered signature before anything is forwarded to the syscall,
+    ///  so a caller cannot silently pack arguments that don't match what the handler registered for.
+    ///
+    /// ## Errors
+    ///
+    /// If `args` does not match the signature `cmd` was registered with, returns `INVALID_OPERATION` without issuing the command.
+    pub fn issue<A: CommandArgs>(&self, cmd: &DeviceCommand, args: A) -> Result<()> {
+        if !args.signature_matches(cmd.sigtys) {
+            return Error::from_code(INVALID_OPERATION);
+        }
+
+        Error::from_code(unsafe { args.issue_raw(self.as_raw(), &cmd.id) })
+    }
+}
+
+/// A device command registered via [`register`], pairing a command id with the signature it was registered with
+///
+/// Pass to [`HandleRef::issue`] to invoke it; the same `sigtys` this was built from is what [`issue`][HandleRef::issue] checks `args` against.
+pub struct DeviceCommand {
+    id: Uuid,
+    sigtys: &'static [DeviceCommandParameter],
+}
+
+impl DeviceCommand {
+    /// The id of this command
+    pub const fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// The parameter signature this command was registered with
+    pub const fn signature(&self) -> &'static [DeviceCommandParameter] {
+        self.sigtys
+    }
+}
+
+/// Registers a capture-free handler for the device command `devid`/`cmdid`
+///
+/// If `cmdid` is the nil UUID, the kernel assigns one and writes it back. `sigtys` is recorded on the
+///  returned [`DeviceCommand`], and [`issue`][HandleRef::issue] checks arguments against it. `handler`'s arguments
+///  are decoded according to the types in `A`; `register` does not check `A` against `sigtys`.
+///
+/// `handler` must be capture-free (a plain `fn` item or a closure with no captures): the raw callback ABI
+///  has no per-registration userdata slot, so it is reconstructed from its (zero-sized) type alone on each call.
+pub fn register<F, A>(
+    devid: &Uuid,
+    cmdid: &mut Uuid,
+    sigtys: &'static [DeviceCommandParameter],
+    handler: F,
+) -> Result<DeviceCommand>
+where
+    A: RegisterArgs,
+    F: Fn(BorrowedHandle<'_, SecurityContext>, A) -> Result<()> + Copy + 'static,
+{
+    let trampoline = A::build_trampoline(handler);
+
+    Error::from_code(unsafe {
+        device::RegisterDeviceCommand(
+            devid,
+            cmdid,
+            trampoline,
+            core::ptr::null_mut(),
+            sigtys.as_ptr(),
+            sigtys.len() as c_ulong,
+        )
+    })?;
+
+    Ok(DeviceCommand { id: *cmdid, sigtys })
+}
@@ -0,0 +1,130 @@
+//! Iteration over the devices present in a device scope
+//!
+//! [`EnumerateDevices`][crate::sys::device::EnumerateDevices] yields one device
+//! at a time by index, without opening it; [`DeviceIter`] walks that binding to
+//! let udev-style userspace services snapshot what devices currently exist in a
+//! namespace (or the current thread's device scope) before deciding which ones,
+//! if any, to open.
+
+use core::ffi::c_ulong;
+
+use alloc::vec;
+
+use crate::{
+    error::Error,
+    fs::PathBuf,
+    handle::{BorrowedHandle, OwnedHandle},
+    sys::{
+        device::{self, DeviceHandle},
+        handle::HandlePtr,
+        isolation::NamespaceHandle,
+        kstr::KStrPtr,
+        result::{INSUFFICIENT_LENGTH, SUCCESS},
+    },
+    uuid::Uuid,
+};
+
+/// A lightweight snapshot of a device present in a device scope at the time it was enumerated
+///
+/// Obtained from a [`DeviceIter`]. Does not hold an open handle to the device -
+/// call [`open`][Self::open] to promote it to one.
+#[derive(Clone, Debug)]
+pub struct DeviceEntry {
+    id: Uuid,
+    label: PathBuf,
+}
+
+impl DeviceEntry {
+    /// The id of the device at the time it was enumerated
+    pub const fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// The label of the device at the time it was enumerated
+    pub fn label(&self) -> &PathBuf {
+        &self.label
+    }
+
+    /// Opens the device this entry refers to, as though by [`OpenDevice`][device::OpenDevice]
+    ///
+    /// This can fail with `UNKNOWN_DEVICE` if the device was removed between enumeration and this call.
+    pub fn open(&self) -> crate::error::Result<OwnedHandle<DeviceHandle>> {
+        let mut hdl = core::mem::MaybeUninit::uninit();
+
+        Error::from_code(unsafe { device::OpenDevice(hdl.as_mut_ptr(), self.id) })?;
+
+        Ok(unsafe { OwnedHandle::take_ownership(hdl.assume_init()) })
+    }
+}
+
+/// An iterator over the devices in a device scope, yielding a [`DeviceEntry`] per device
+///
+/// Constructed with [`DeviceIter::current_thread`] or [`DeviceIter::in_namespace`].
+pub struct DeviceIter<'a> {
+    ns: Option<BorrowedHandle<'a, NamespaceHandle>>,
+    idx: c_ulong,
+    done: bool,
+}
+
+impl<'a> DeviceIter<'a> {
+    /// Enumerates the device scope of the current thread
+    pub const fn current_thread() -> Self {
+        Self {
+            ns: None,
+            idx: 0,
+            done: false,
+        }
+    }
+
+    /// Enumerates the device scope of `ns`
+    pub const fn in_namespace(ns: BorrowedHandle<'a, NamespaceHandle>) -> Self {
+        Self {
+            ns: Some(ns),
+            idx: 0,
+            done: false,
+        }
+    }
+}
+
+impl<'a> Iterator for DeviceIter<'a> {
+    type Item = DeviceEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let ns = self.ns.map_or(HandlePtr::null(), |ns| ns.as_raw());
+
+        let mut id = Uuid::nil();
+        let mut stack_buf = [0u8; 256];
+        let mut heap_buf;
+        let mut label = KStrPtr::from_slice(&mut stack_buf);
+
+        loop {
+            let code = unsafe { device::EnumerateDevices(ns, self.idx, &mut id, &mut label) };
+
+            if code == SUCCESS {
+                self.idx += 1;
+                return Some(DeviceEntry {
+                    id,
+                    label: PathBuf::from(label.as_str()),
+                });
+            }
+
+            if code == INSUFFICIENT_LENGTH {
+                // `label` was too small; the kernel stored the required length in
+                // `label.len()` - grow to fit and retry the same index rather than
+                // treating the truncation as the end of the enumeration.
+                heap_buf = vec![0u8; label.len()];
+                label = KStrPtr::from_slice(&mut heap_buf);
+                continue;
+            }
+
+            // Out of devices (`INVALID_OPERATION`) or some other failure - either way,
+            // there is nothing more to yield.
+            self.done = true;
+            return None;
+        }
+    }
+}
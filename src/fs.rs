@@ -1,6 +1,6 @@
 use core::{str::Split, ops::Deref, borrow::Borrow};
 
-use alloc::{string::{ToString, String}, borrow::Cow};
+use alloc::{string::{ToString, String}, borrow::Cow, vec::Vec};
 
 use crate::sys::kstr::KStrCPtr;
 
@@ -74,6 +74,91 @@ impl Path{
             .map(Path::new)
     }
 
+    /// Returns everything before the final [`file_name`][Self::file_name], or `None` if this path has no `/`
+    pub fn parent(&self) -> Option<&Path>{
+        self.0.rsplit_once("/")
+            .map(|(a,_)| if a.is_empty() { "/" } else { a })
+            .map(Path::new)
+    }
+
+    /// Returns the portion of [`file_name`][Self::file_name] after its last `.`, or `None` if it has no extension
+    ///
+    /// A name consisting of only a leading `.` (such as `.bashrc`) is considered to have no extension.
+    pub fn extension(&self) -> Option<&str>{
+        let name = self.file_name()?.as_str();
+        let (stem, ext) = name.rsplit_once('.')?;
+        if stem.is_empty(){
+            None
+        }else{
+            Some(ext)
+        }
+    }
+
+    /// Joins `self` with `path`, as though by [`PathBuf::push`]
+    pub fn join<P: AsRef<Path> + ?Sized>(&self, path: &P) -> PathBuf{
+        let mut buf = self.to_path_buf();
+        buf.push(path);
+        buf
+    }
+
+    /// Performs purely lexical normalization of this path, without touching the filesystem
+    ///
+    /// Walks [`components`][Self::components] into a stack: `.` components are dropped, a `..` pops the last
+    ///  real component (or, for a relative path, accumulates as a leading `..` if there is nothing left to pop),
+    ///  and a `/` root clears the stack and marks the result absolute.
+    pub fn normalize(&self) -> PathBuf{
+        enum Segment<'a>{
+            Real(&'a Path),
+            ParentDir,
+        }
+
+        let mut stack: Vec<Segment> = Vec::new();
+        let mut absolute = false;
+
+        for c in self.components(){
+            match c{
+                Component::Root => {
+                    stack.clear();
+                    absolute = true;
+                }
+                Component::CurDir => {}
+                Component::ParentDir => match stack.last(){
+                    Some(Segment::Real(_)) => { stack.pop(); }
+                    Some(Segment::ParentDir) => stack.push(Segment::ParentDir),
+                    None => if !absolute{
+                        stack.push(Segment::ParentDir);
+                    }
+                },
+                // Skip empty segments (from `//` or a trailing `/`) so they don't survive into the result.
+                Component::RealPath(seg) if seg.as_str().is_empty() => {}
+                Component::RealPath(seg) => stack.push(Segment::Real(seg)),
+            }
+        }
+
+        let mut result = String::new();
+
+        if absolute{
+            result.push('/');
+        }
+
+        for (i, seg) in stack.iter().enumerate(){
+            if i > 0{
+                result.push('/');
+            }
+
+            match seg{
+                Segment::Real(seg) => result.push_str(seg.as_str()),
+                Segment::ParentDir => result.push_str(".."),
+            }
+        }
+
+        if stack.is_empty() && !absolute{
+            result.push('.');
+        }
+
+        PathBuf::from_string(result)
+    }
+
     pub fn components(&self) -> Components{
         let next_is_root = self.0.starts_with("/");
         Components { next_is_root, split: self.0.split('/') }
@@ -137,6 +222,24 @@ impl PathBuf{
     pub fn as_path(&self) -> &Path{
         Path::new(&self.0)
     }
+
+    /// Appends `path` onto `self`
+    ///
+    /// If `path` is absolute (begins with `/`), it replaces the contents of `self` rather than being appended
+    pub fn push<P: AsRef<Path> + ?Sized>(&mut self, path: &P){
+        let path = path.as_ref();
+
+        if path.as_str().starts_with('/'){
+            self.0.clear();
+            self.0.push_str(path.as_str());
+        }else{
+            if !self.0.is_empty() && !self.0.ends_with('/'){
+                self.0.push('/');
+            }
+
+            self.0.push_str(path.as_str());
+        }
+    }
 }
 
 impl Deref for PathBuf{
@@ -156,4 +259,43 @@ impl Borrow<Path> for PathBuf{
     fn borrow(&self) -> &Path{
         self.as_path()
     }
+}
+
+#[cfg(test)]
+mod tests{
+    use super::Path;
+
+    #[test]
+    fn normalize_collapses_repeated_and_trailing_slashes(){
+        assert_eq!(Path::new("a//b").normalize().as_path(), Path::new("a/b"));
+        assert_eq!(Path::new("a/b/").normalize().as_path(), Path::new("a/b"));
+    }
+
+    #[test]
+    fn normalize_resolves_parent_dir(){
+        assert_eq!(Path::new("a/b/../c").normalize().as_path(), Path::new("a/c"));
+        assert_eq!(Path::new("../a").normalize().as_path(), Path::new("../a"));
+        assert_eq!(Path::new("/../a").normalize().as_path(), Path::new("/a"));
+    }
+
+    #[test]
+    fn normalize_empty_and_current_dir(){
+        assert_eq!(Path::new("").normalize().as_path(), Path::new("."));
+        assert_eq!(Path::new(".").normalize().as_path(), Path::new("."));
+        assert_eq!(Path::new("/").normalize().as_path(), Path::new("/"));
+    }
+
+    #[test]
+    fn parent_of_single_absolute_segment_is_root(){
+        assert_eq!(Path::new("/foo").parent(), Some(Path::new("/")));
+        assert_eq!(Path::new("foo").parent(), None);
+        assert_eq!(Path::new("a/b").parent(), Some(Path::new("a")));
+    }
+
+    #[test]
+    fn extension_ignores_leading_dot_only_names(){
+        assert_eq!(Path::new("/dir/.bashrc").extension(), None);
+        assert_eq!(Path::new("/dir/a.tar.gz").extension(), Some("gz"));
+        assert_eq!(Path::new("/dir/a").extension(), None);
+    }
 }
\ No newline at end of file
@@ -150,7 +150,14 @@ impl<'a, T> Clone for BorrowedHandle<'a, T> {
 
 impl<'a, T> Copy for BorrowedHandle<'a, T> {}
 
-impl<'a, T: HandleType> BorrowedHandle<'a, T> {}
+impl<'a, T: HandleType> BorrowedHandle<'a, T> {
+    /// Borrows a handle from a raw pointer for the lifetime `'a`
+    ///
+    /// The caller must ensure `hdl` refers to a valid handle of the right type, which is not destroyed for at least `'a`
+    pub const unsafe fn from_raw(hdl: HandlePtr<T>) -> Self {
+        Self(hdl, PhantomData)
+    }
+}
 
 impl<'a, T> Deref for BorrowedHandle<'a, T> {
     type Target = HandleRef<T>;
@@ -51,6 +51,34 @@ pub struct CharDeviceConfiguration {
     pub optimistic_io_size: u64,
 }
 
+/// A point in time with nanosecond resolution, as reported by [`GetDeviceGeometry`]/[`GetFileDeviceGeometry`]
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DeviceTimestamp {
+    /// Seconds since the epoch
+    pub seconds: i64,
+    /// Nanoseconds within the second given by `seconds`
+    pub nanoseconds: u32,
+}
+
+/// Geometry and timestamp information about a device, as reported by [`GetDeviceGeometry`]/[`GetFileDeviceGeometry`]
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DeviceGeometry {
+    /// The smallest unit of addressable storage on the device
+    pub logical_block_size: u64,
+    /// The block size the device reports as optimistic, IE. performing I/O in units of this size is at least as efficient as any smaller unit
+    pub physical_block_size: u64,
+    /// The total number of `logical_block_size` blocks on the device. Zero for character devices, which have no fixed layout
+    pub block_count: u64,
+    /// The last time the device was read from
+    pub access_time: DeviceTimestamp,
+    /// The last time the device's contents were modified
+    pub modify_time: DeviceTimestamp,
+    /// The last time the device's metadata (including this geometry) was changed
+    pub change_time: DeviceTimestamp,
+}
+
 /// A Handle to a device
 #[repr(transparent)]
 pub struct DeviceHandle(Handle);
@@ -182,13 +210,45 @@ extern "C" {
     pub fn OpenDevice(hdl: *mut HandlePtr<DeviceHandle>, id: Uuid) -> SysResult;
     pub fn CloseDevice(hdl: HandlePtr<DeviceHandle>) -> SysResult;
 
+    /// Yields the id and label of the `idx`th device (0-based) in the device scope of `ns`, without opening it.
+    ///
+    /// If `ns` is not specified, enumerates the device scope of the current thread.
+    ///
+    /// Devices are not guaranteed to be returned in any particular order, and the order is not guaranteed to be stable
+    ///  across calls if devices are concurrently created or removed in the same scope.
+    ///
+    /// ## Errors
+    ///
+    /// If `idx` is not less than the number of devices currently in the device scope, returns `INVALID_OPERATION`.
+    ///
+    /// If `label` is not `NULL` and does not refer to a buffer large enough to store the label, returns `INSUFFICIENT_LENGTH`,
+    ///  and the required length is stored in `label->len`.
+    ///
+    pub fn EnumerateDevices(
+        ns: HandlePtr<NamespaceHandle>,
+        idx: c_ulong,
+        id: *mut Uuid,
+        label: *mut KStrPtr,
+    ) -> SysResult;
+
     pub fn GetDeviceLabel(hdl: HandlePtr<DeviceHandle>, label: *mut KStrPtr) -> SysResult;
     pub fn GetOptimisticIOSize(hdl: HandlePtr<DeviceHandle>, io_size: *mut u64) -> SysResult;
     pub fn GetDeviceId(hdl: HandlePtr<DeviceHandle>, id: *mut Uuid) -> SysResult;
 
+    /// Retrieves the block layout and timestamps of the device referred to by `hdl`.
+    ///
+    /// `block_count` is reported as `0` for character devices.
+    ///
+    /// ## Errors
+    ///
+    /// If `hdl` is not a valid [`DeviceHandle`], returns `INVALID_HANDLE`.
+    pub fn GetDeviceGeometry(hdl: HandlePtr<DeviceHandle>, geometry: *mut DeviceGeometry) -> SysResult;
+
     pub fn GetFileDeviceLabel(hdl: HandlePtr<FileHandle>, label: *mut KStrPtr) -> SysResult;
     pub fn GetFileOptimisticIOSize(hdl: HandlePtr<FileHandle>, io_size: *mut u64) -> SysResult;
     pub fn GetFileDeviceId(hdl: HandlePtr<FileHandle>, id: *mut Uuid) -> SysResult;
+    /// Equivalent to [`GetDeviceGeometry`], but for the device backing the mounted filesystem that `hdl` resides on
+    pub fn GetFileDeviceGeometry(hdl: HandlePtr<FileHandle>, geometry: *mut DeviceGeometry) -> SysResult;
     pub fn OpenDeviceFromFile(
         devhdl: *mut HandlePtr<DeviceHandle>,
         file: HandlePtr<FileHandle>,
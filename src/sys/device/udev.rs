@@ -0,0 +1,42 @@
+//! Raw ABI descriptors for device command registration and dispatch
+//!
+//! These types describe the variadic argument lists accepted by
+//! [`IssueDeviceCommand`][super::IssueDeviceCommand] and
+//! [`RegisterDeviceCommand`][super::RegisterDeviceCommand]; the kernel and the
+//! issuing process both interpret a command's variadic arguments according to
+//! the same `sigtys` array, so the shape described here must match on both
+//! sides of the syscall.
+
+use core::ffi::c_ulong;
+
+/// The kind of a single device command parameter slot
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DeviceCommandParameterKind {
+    /// A 32-bit signed integer, passed by value
+    Int32 = 0,
+    /// A 64-bit signed integer, passed by value
+    Int64 = 1,
+    /// A 32-bit unsigned integer, passed by value
+    UInt32 = 2,
+    /// A 64-bit unsigned integer, passed by value
+    UInt64 = 3,
+    /// A handle, passed by value
+    Handle = 4,
+    /// A buffer of `size` bytes, passed as a pointer
+    Buffer = 5,
+}
+
+/// Describes a single parameter slot in a device command's signature
+///
+/// An array of these is given to [`RegisterDeviceCommand`][super::RegisterDeviceCommand]
+/// and is the single source of truth that [`IssueDeviceCommand`][super::IssueDeviceCommand]
+/// callers are checked against.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DeviceCommandParameter {
+    /// The kind of value expected in this slot
+    pub kind: DeviceCommandParameterKind,
+    /// For [`DeviceCommandParameterKind::Buffer`], the required length of the buffer in bytes. Ignored for other kinds.
+    pub size: c_ulong,
+}